@@ -0,0 +1,332 @@
+use std::io::BufRead;
+use std::str::FromStr;
+
+use duplicate::duplicate;
+
+use crate::{Bodyfile3Line, ParseError, ParseErrorKind};
+
+/// The number of `|`-separated fields in a TSK 3.x bodyfile line.
+const V3_FIELDS: usize = 11;
+
+/// The number of `|`-separated fields in the legacy TSK 1.x/2.x layout, as
+/// emitted by the pre-3.0 `fls`/`mac-robber` tools.
+const V1_FIELDS: usize = 15;
+
+/// A line in the legacy TSK 1.x/2.x bodyfile format.
+///
+/// Unlike the 3.x layout (see [`Bodyfile3Line`]), the old format carries the
+/// device, link count, `rdev` and block accounting fields, lacks a creation
+/// time entirely, and orders its columns as
+///
+/// ```text
+/// MD5|name|device|inode|mode|num_links|UID|GID|rdev|size|atime|mtime|ctime|block_size|num_blocks
+/// ```
+pub struct Bodyfile1Line {
+    md5: String,
+    name: String,
+    device: i64,
+    inode: String,
+    mode_as_string: String,
+    num_links: i64,
+    uid: i64,
+    gid: i64,
+    rdev: i64,
+    size: i64,
+    atime: i64,
+    mtime: i64,
+    ctime: i64,
+    block_size: i64,
+    num_blocks: i64,
+}
+
+impl Bodyfile1Line {
+    #[duplicate(
+        method_name     attribute_name;
+        [get_md5]       [md5];
+        [get_name]      [name];
+        [get_inode]     [inode];
+        [get_mode]      [mode_as_string];
+    )]
+    pub fn method_name(&self) -> &str {
+        &self.attribute_name
+    }
+
+    #[duplicate(
+        method_name        attribute_name;
+        [get_device]       [device];
+        [get_num_links]    [num_links];
+        [get_uid]          [uid];
+        [get_gid]          [gid];
+        [get_rdev]         [rdev];
+        [get_size]         [size];
+        [get_atime]        [atime];
+        [get_mtime]        [mtime];
+        [get_ctime]        [ctime];
+        [get_block_size]   [block_size];
+        [get_num_blocks]   [num_blocks];
+    )]
+    pub fn method_name(&self) -> i64 {
+        self.attribute_name
+    }
+}
+
+/// Parses a single legacy (1.x/2.x) bodyfile line.
+///
+/// The line is split on `|` into exactly 15 fields; every field except md5,
+/// name, inode and mode is parsed as `i64`. As with [`Bodyfile3Line`]'s parser,
+/// comment and blank lines are the caller's concern.
+///
+/// # Example
+/// ```
+/// use bodyfile::Bodyfile1Line;
+///
+/// // fields 10/11/12 are atime/mtime/ctime, in that order
+/// let line: Bodyfile1Line =
+///     "0|/a|0|5|-rwxr-xr-x|1|0|0|0|123|10|20|30|512|1".parse().unwrap();
+/// assert_eq!(line.get_inode(), "5");
+/// assert_eq!(line.get_atime(), 10);
+/// assert_eq!(line.get_mtime(), 20);
+/// assert_eq!(line.get_ctime(), 30);
+/// ```
+impl FromStr for Bodyfile1Line {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.strip_suffix('\n').unwrap_or(s);
+        let s = s.strip_suffix('\r').unwrap_or(s);
+
+        let fields: Vec<&str> = s.split('|').collect();
+        if fields.len() != V1_FIELDS {
+            return Err(ParseError {
+                line: 0,
+                kind: ParseErrorKind::FieldCount(fields.len()),
+            });
+        }
+
+        let as_i64 = |field: &'static str, value: &str| {
+            value.parse::<i64>().map_err(|_| ParseError {
+                line: 0,
+                kind: ParseErrorKind::InvalidInteger {
+                    field,
+                    value: value.to_owned(),
+                },
+            })
+        };
+
+        Ok(Self {
+            md5: fields[0].to_owned(),
+            name: fields[1].to_owned(),
+            device: as_i64("device", fields[2])?,
+            inode: fields[3].to_owned(),
+            mode_as_string: fields[4].to_owned(),
+            num_links: as_i64("num_links", fields[5])?,
+            uid: as_i64("UID", fields[6])?,
+            gid: as_i64("GID", fields[7])?,
+            rdev: as_i64("rdev", fields[8])?,
+            size: as_i64("size", fields[9])?,
+            atime: as_i64("atime", fields[10])?,
+            mtime: as_i64("mtime", fields[11])?,
+            ctime: as_i64("ctime", fields[12])?,
+            block_size: as_i64("block_size", fields[13])?,
+            num_blocks: as_i64("num_blocks", fields[14])?,
+        })
+    }
+}
+
+/// Conversion of a legacy line into the modern representation.
+///
+/// The overlapping fields (md5, name, inode, mode, uid, gid, size and the
+/// atime/mtime/ctime times) are carried across verbatim; `crtime` is set to
+/// the `-1` "unset" sentinel because the 1.x format has no creation time.
+/// The device, num_links, rdev, block_size and num_blocks fields have no 3.x
+/// counterpart and are dropped.
+impl From<Bodyfile1Line> for Bodyfile3Line {
+    fn from(line: Bodyfile1Line) -> Self {
+        Bodyfile3Line::from_values(
+            line.md5,
+            line.name,
+            line.inode,
+            line.mode_as_string,
+            line.uid,
+            line.gid,
+            line.size,
+            line.atime,
+            line.mtime,
+            line.ctime,
+            -1,
+        )
+    }
+}
+
+/// A bodyfile line of either supported version.
+///
+/// Parsing a [`BodyfileLine`] auto-detects the format from the number of
+/// `|`-separated fields — 11 for the 3.x layout, 15 for the legacy 1.x/2.x
+/// layout — and then confirms the guess by checking that the time columns of
+/// that version actually parse as integers, rejecting lines that merely happen
+/// to have the right field count. This lets a single reader API ingest
+/// historical bodyfiles produced by older `fls`/`mac-robber` versions and
+/// normalize them to the modern [`Bodyfile3Line`] with
+/// [`into_bodyfile3`](BodyfileLine::into_bodyfile3).
+///
+/// Detection assumes the `name` and `inode` fields do not themselves contain an
+/// embedded `|`; such a value would shift the field count and is not supported,
+/// matching the delimiter assumptions of the underlying format.
+///
+/// # Example
+/// ```
+/// use bodyfile::BodyfileLine;
+///
+/// let legacy = "0|/a|0|5|-rwxr-xr-x|1|0|0|0|123|10|20|30|512|1";
+/// let line: BodyfileLine = legacy.parse().unwrap();
+/// assert!(matches!(line, BodyfileLine::Version1(_)));
+///
+/// let bf = line.into_bodyfile3();
+/// assert_eq!(bf.get_size(), 123);
+/// assert_eq!(bf.get_atime(), 10);
+/// assert_eq!(bf.get_mtime(), 20);
+/// assert_eq!(bf.get_crtime(), -1); // the 1.x format has no creation time
+/// ```
+pub enum BodyfileLine {
+    Version1(Bodyfile1Line),
+    Version3(Bodyfile3Line),
+}
+
+impl BodyfileLine {
+    /// Normalize either version into a [`Bodyfile3Line`].
+    pub fn into_bodyfile3(self) -> Bodyfile3Line {
+        match self {
+            BodyfileLine::Version1(line) => line.into(),
+            BodyfileLine::Version3(line) => line,
+        }
+    }
+}
+
+impl FromStr for BodyfileLine {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.strip_suffix('\n').unwrap_or(s);
+        let trimmed = trimmed.strip_suffix('\r').unwrap_or(trimmed);
+
+        let fields: Vec<&str> = trimmed.split('|').collect();
+
+        // `all_ints` confirms the time columns of a guessed version parse as
+        // integers, so a line with the right field count but the wrong shape is
+        // rejected rather than silently misclassified; such a line is handed to
+        // that version's parser, whose error names the offending field.
+        let all_ints = |indices: &[usize]| {
+            indices
+                .iter()
+                .all(|&i| fields[i].parse::<i64>().is_ok())
+        };
+
+        // 3.x time columns: atime/mtime/ctime/crtime; 1.x: atime/mtime/ctime
+        match fields.len() {
+            V3_FIELDS if all_ints(&[7, 8, 9, 10]) => {
+                Ok(BodyfileLine::Version3(trimmed.parse()?))
+            }
+            V1_FIELDS if all_ints(&[10, 11, 12]) => {
+                Ok(BodyfileLine::Version1(trimmed.parse()?))
+            }
+            V3_FIELDS => Bodyfile3Line::from_str(trimmed).map(BodyfileLine::Version3),
+            V1_FIELDS => Bodyfile1Line::from_str(trimmed).map(BodyfileLine::Version1),
+            other => Err(ParseError {
+                line: 0,
+                kind: ParseErrorKind::FieldCount(other),
+            }),
+        }
+    }
+}
+
+/// Reads bodyfile records of either version from any [`BufRead`], normalizing
+/// each to a [`Bodyfile3Line`].
+///
+/// This is the legacy-aware counterpart to [`Bodyfile3Reader`](crate::Bodyfile3Reader):
+/// it applies the same tolerant-parsing rules — lines whose first
+/// non-whitespace character is `#` are skipped as comments, blank lines are
+/// ignored, and a malformed record yields a structured [`ParseError`] carrying
+/// its line number without ending the stream — while auto-detecting the format
+/// of every line via [`BodyfileLine`]. A single reader can therefore ingest a
+/// file mixing (or wholly consisting of) historical 1.x/2.x records and hand
+/// back the modern representation.
+///
+/// # Example
+/// ```
+/// use bodyfile::BodyfileReader;
+///
+/// let input = "\
+/// ## a legacy (1.x) record
+/// 0|/a|0|5|-rwxr-xr-x|1|0|0|0|123|10|20|30|512|1
+/// 0|/b|7|-|0|0|40|1|2|3|4
+/// ";
+/// let mut reader = BodyfileReader::from(input.as_bytes());
+///
+/// let a = reader.next().unwrap().unwrap();
+/// assert_eq!(a.get_name(), "/a");
+/// assert_eq!(a.get_atime(), 10);
+/// assert_eq!(a.get_crtime(), -1); // normalized from 1.x
+///
+/// let b = reader.next().unwrap().unwrap();
+/// assert_eq!(b.get_name(), "/b");
+/// assert_eq!(b.get_crtime(), 4); // native 3.x
+///
+/// assert!(reader.next().is_none());
+/// ```
+pub struct BodyfileReader<R: BufRead> {
+    inner: R,
+    line_number: usize,
+}
+
+impl<R: BufRead> BodyfileReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            line_number: 0,
+        }
+    }
+}
+
+impl<R: BufRead> From<R> for BodyfileReader<R> {
+    fn from(inner: R) -> Self {
+        Self::new(inner)
+    }
+}
+
+impl<R: BufRead> Iterator for BodyfileReader<R> {
+    type Item = Result<Bodyfile3Line, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buf = String::new();
+        loop {
+            buf.clear();
+            match self.inner.read_line(&mut buf) {
+                Ok(0) => return None,
+                Ok(_) => {}
+                Err(e) => {
+                    self.line_number += 1;
+                    return Some(Err(ParseError {
+                        line: self.line_number,
+                        kind: ParseErrorKind::Io(e),
+                    }));
+                }
+            }
+            self.line_number += 1;
+
+            let trimmed = buf.trim_end_matches(['\r', '\n']);
+            let leading = trimmed.trim_start();
+            if leading.is_empty() || leading.starts_with('#') {
+                continue;
+            }
+
+            return Some(
+                BodyfileLine::from_str(trimmed)
+                    .map(BodyfileLine::into_bodyfile3)
+                    .map_err(|mut e| {
+                        e.line = self.line_number;
+                        e
+                    }),
+            );
+        }
+    }
+}
@@ -1,4 +1,9 @@
+use std::fmt;
+use std::io::BufRead;
+use std::str::FromStr;
+
 use duplicate::duplicate;
+use time::OffsetDateTime;
 
 /// Quote from <https://wiki.sleuthkit.org/index.php?title=Body_file>:
 /// 
@@ -164,4 +169,587 @@ impl ToString for Bodyfile3Line {
             self.crtime
         )
     }
-}
\ No newline at end of file
+}
+
+/// Describes why a single bodyfile line could not be parsed.
+///
+/// The companion [`ParseError`] pairs one of these with the line number at
+/// which it occurred, so that a reader iterating a large timeline can report
+/// exactly which record was rejected and keep going.
+#[derive(Debug)]
+pub enum ParseErrorKind {
+    /// the line did not split into the number of fields its format expects
+    /// (11 for TSK 3.x, 15 for the legacy 1.x/2.x layout); holds the count found
+    FieldCount(usize),
+
+    /// one of the integer fields could not be parsed as `i64`
+    InvalidInteger {
+        field: &'static str,
+        value: String,
+    },
+
+    /// the underlying reader returned an I/O error
+    Io(std::io::Error),
+}
+
+/// A parse failure for a single bodyfile line.
+///
+/// `line` is the 1-based line number within the stream, or `0` when the line
+/// was parsed in isolation (for example through [`FromStr`]). The [`Bodyfile3Reader`]
+/// fills in the correct line number as it iterates.
+#[derive(Debug)]
+pub struct ParseError {
+    pub line: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl ParseError {
+    fn new(kind: ParseErrorKind) -> Self {
+        Self { line: 0, kind }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ParseErrorKind::FieldCount(found) => write!(
+                f,
+                "line {}: unexpected number of fields: {}",
+                self.line, found
+            ),
+            ParseErrorKind::InvalidInteger { field, value } => write!(
+                f,
+                "line {}: field '{}' is not a valid integer: '{}'",
+                self.line, field, value
+            ),
+            ParseErrorKind::Io(e) => write!(f, "line {}: {}", self.line, e),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            ParseErrorKind::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Parses a single bodyfile line into a [`Bodyfile3Line`].
+///
+/// The line is split on the `|` delimiter into exactly 11 fields; the four
+/// time fields together with `UID`, `GID` and `size` are parsed as `i64`,
+/// while md5, name, inode and mode are kept verbatim. A trailing newline is
+/// tolerated so that lines read straight from a file round-trip cleanly.
+///
+/// Comment (`#`) and blank lines are *not* handled here — they are the
+/// responsibility of [`Bodyfile3Reader`], which skips them before parsing.
+///
+/// # Example
+/// ```
+/// use bodyfile::Bodyfile3Line;
+///
+/// let line = "0|/etc/passwd|12|-rwxr-xr-x|0|0|1234|1|2|3|4";
+/// let bf: Bodyfile3Line = line.parse().unwrap();
+/// assert_eq!(bf.get_name(), "/etc/passwd");
+/// assert_eq!(bf.get_size(), 1234);
+/// assert_eq!(bf.get_crtime(), 4);
+/// ```
+impl FromStr for Bodyfile3Line {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.strip_suffix('\n').unwrap_or(s);
+        let s = s.strip_suffix('\r').unwrap_or(s);
+
+        let fields: Vec<&str> = s.split('|').collect();
+        if fields.len() != 11 {
+            return Err(ParseError::new(ParseErrorKind::FieldCount(fields.len())));
+        }
+
+        let as_i64 = |field: &'static str, value: &str| {
+            value.parse::<i64>().map_err(|_| {
+                ParseError::new(ParseErrorKind::InvalidInteger {
+                    field,
+                    value: value.to_owned(),
+                })
+            })
+        };
+
+        Ok(Self {
+            md5: fields[0].to_owned(),
+            name: fields[1].to_owned(),
+            inode: fields[2].to_owned(),
+            mode_as_string: fields[3].to_owned(),
+            uid: as_i64("UID", fields[4])?,
+            gid: as_i64("GID", fields[5])?,
+            size: as_i64("size", fields[6])?,
+            atime: as_i64("atime", fields[7])?,
+            mtime: as_i64("mtime", fields[8])?,
+            ctime: as_i64("ctime", fields[9])?,
+            crtime: as_i64("crtime", fields[10])?,
+        })
+    }
+}
+
+/// Reads [`Bodyfile3Line`] records from any [`BufRead`], one line at a time.
+///
+/// The reader follows the tolerant-parsing approach mandated by the TSK
+/// format: lines whose first non-whitespace character is `#` are skipped as
+/// comments and blank lines are ignored, while a malformed record yields a
+/// structured [`ParseError`] (carrying its line number) *without* ending the
+/// stream. A caller iterating a multi-gigabyte timeline can therefore
+/// log-and-continue past a single bad record instead of losing everything.
+///
+/// # Example
+/// ```
+/// use bodyfile::Bodyfile3Reader;
+///
+/// let input = "\
+/// ## this is a comment
+/// 0|/a|1|-|0|0|0|1|1|1|1
+///
+/// not a valid line
+/// 0|/b|2|-|0|0|0|2|2|2|2
+/// ";
+/// let mut reader = Bodyfile3Reader::from(input.as_bytes());
+/// assert_eq!(reader.next().unwrap().unwrap().get_name(), "/a");
+/// assert!(reader.next().unwrap().is_err());
+/// assert_eq!(reader.next().unwrap().unwrap().get_name(), "/b");
+/// assert!(reader.next().is_none());
+/// ```
+pub struct Bodyfile3Reader<R: BufRead> {
+    inner: R,
+    line_number: usize,
+}
+
+impl<R: BufRead> Bodyfile3Reader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            line_number: 0,
+        }
+    }
+}
+
+impl<R: BufRead> From<R> for Bodyfile3Reader<R> {
+    fn from(inner: R) -> Self {
+        Self::new(inner)
+    }
+}
+
+impl<R: BufRead> Iterator for Bodyfile3Reader<R> {
+    type Item = Result<Bodyfile3Line, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buf = String::new();
+        loop {
+            buf.clear();
+            match self.inner.read_line(&mut buf) {
+                Ok(0) => return None,
+                Ok(_) => {}
+                Err(e) => {
+                    self.line_number += 1;
+                    return Some(Err(ParseError {
+                        line: self.line_number,
+                        kind: ParseErrorKind::Io(e),
+                    }));
+                }
+            }
+            self.line_number += 1;
+
+            let trimmed = buf.trim_end_matches(['\r', '\n']);
+            let leading = trimmed.trim_start();
+            if leading.is_empty() || leading.starts_with('#') {
+                continue;
+            }
+
+            return Some(Bodyfile3Line::from_str(trimmed).map_err(|mut e| {
+                e.line = self.line_number;
+                e
+            }));
+        }
+    }
+}
+/// The kind of filesystem object a bodyfile record describes.
+///
+/// This mirrors the distinctions made by [`std::fs::FileType`] but is derived
+/// from the leading character of the `mode_as_string` field rather than from a
+/// live `stat`, so it can be recovered from a bodyfile read back off disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileType {
+    RegularFile,
+    Directory,
+    Symlink,
+    BlockDevice,
+    CharDevice,
+    Fifo,
+    Socket,
+    Unknown,
+}
+
+impl FileType {
+    fn from_char(c: char) -> Self {
+        match c {
+            '-' => FileType::RegularFile,
+            'd' => FileType::Directory,
+            'l' => FileType::Symlink,
+            'b' => FileType::BlockDevice,
+            'c' => FileType::CharDevice,
+            'p' => FileType::Fifo,
+            's' => FileType::Socket,
+            _ => FileType::Unknown,
+        }
+    }
+
+    /// the high `st_mode` bits (`S_IFMT`) corresponding to this type
+    fn mode_bits(&self) -> u32 {
+        match self {
+            FileType::RegularFile => 0o100000,
+            FileType::Directory => 0o040000,
+            FileType::Symlink => 0o120000,
+            FileType::BlockDevice => 0o060000,
+            FileType::CharDevice => 0o020000,
+            FileType::Fifo => 0o010000,
+            FileType::Socket => 0o140000,
+            FileType::Unknown => 0,
+        }
+    }
+}
+
+/// A decoded `mode_as_string`, giving the same ergonomics as
+/// [`std::fs::Permissions`] / [`std::fs::FileType`] without re-parsing the
+/// string by hand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Permissions {
+    file_type: FileType,
+    mode: u32,
+}
+
+impl Permissions {
+    pub fn new(file_type: FileType, mode: u32) -> Self {
+        Self { file_type, mode }
+    }
+
+    pub fn file_type(&self) -> FileType {
+        self.file_type
+    }
+
+    /// the raw `st_mode` bits, including the type (`S_IFMT`), setuid/setgid,
+    /// sticky and the nine `rwx` permission bits
+    pub fn mode(&self) -> u32 {
+        self.mode
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.file_type == FileType::RegularFile
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.file_type == FileType::Directory
+    }
+
+    pub fn is_symlink(&self) -> bool {
+        self.file_type == FileType::Symlink
+    }
+}
+
+/// Translate the nine `rwx` permission characters into mode bits, honoring the
+/// setuid/setgid (`s`/`S`) and sticky (`t`/`T`) markers in the execute column.
+fn parse_permission_bits(perm: &str) -> Option<u32> {
+    let chars: Vec<char> = perm.chars().collect();
+    if chars.len() != 9 {
+        return None;
+    }
+
+    const R: [u32; 3] = [0o400, 0o040, 0o004];
+    const W: [u32; 3] = [0o200, 0o020, 0o002];
+    const X: [u32; 3] = [0o100, 0o010, 0o001];
+    const SPECIAL: [u32; 3] = [0o4000, 0o2000, 0o1000];
+
+    let mut mode = 0u32;
+    for triad in 0..3 {
+        match chars[triad * 3] {
+            'r' => mode |= R[triad],
+            '-' => {}
+            _ => return None,
+        }
+        match chars[triad * 3 + 1] {
+            'w' => mode |= W[triad],
+            '-' => {}
+            _ => return None,
+        }
+        match chars[triad * 3 + 2] {
+            'x' => mode |= X[triad],
+            '-' => {}
+            's' | 't' => mode |= X[triad] | SPECIAL[triad],
+            'S' | 'T' => mode |= SPECIAL[triad],
+            _ => return None,
+        }
+    }
+    Some(mode)
+}
+
+impl Bodyfile3Line {
+    /// Decode `mode_as_string` into a [`FileType`] and the raw `u32` mode bits.
+    ///
+    /// The leading character selects the type (`-`, `d`, `l`, `b`, `c`, `p`,
+    /// `s`), and the following nine characters are the familiar `rwx` triads.
+    /// TSK's dual-prefix form (`x/drwx------`) is accepted by taking the second
+    /// component. Returns `None` if the field is empty or malformed.
+    ///
+    /// # Example
+    /// ```
+    /// use bodyfile::{Bodyfile3Line, FileType};
+    ///
+    /// let bf = Bodyfile3Line::new().with_mode("d/drwxr-xr-x".to_owned());
+    /// let (ft, mode) = bf.parse_mode().unwrap();
+    /// assert_eq!(ft, FileType::Directory);
+    /// assert_eq!(mode, 0o040755);
+    /// ```
+    pub fn parse_mode(&self) -> Option<(FileType, u32)> {
+        let component = self.mode_as_string.rsplit('/').next()?;
+        let mut chars = component.chars();
+        let file_type = FileType::from_char(chars.next()?);
+        let bits = parse_permission_bits(chars.as_str())?;
+        Some((file_type, bits | file_type.mode_bits()))
+    }
+
+    /// Like [`parse_mode`](Self::parse_mode), but returns a [`Permissions`]
+    /// value offering `is_file()`/`is_dir()`/`is_symlink()` accessors.
+    ///
+    /// # Example
+    /// ```
+    /// use bodyfile::Bodyfile3Line;
+    ///
+    /// let bf = Bodyfile3Line::new().with_mode("-rwxr-xr-x".to_owned());
+    /// let perms = bf.permissions().unwrap();
+    /// assert!(perms.is_file());
+    /// assert!(!perms.is_dir());
+    /// ```
+    pub fn permissions(&self) -> Option<Permissions> {
+        self.parse_mode()
+            .map(|(file_type, mode)| Permissions::new(file_type, mode))
+    }
+}
+
+/// Render `st_mode` bits back into the `mode_as_string` form used by the
+/// bodyfile format, inverse to [`Bodyfile3Line::parse_mode`].
+#[cfg(feature = "from_path")]
+fn mode_string_from_bits(mode: u32) -> String {
+    fn push_triad(s: &mut String, mode: u32, r: u32, w: u32, x: u32, special: u32, set: char) {
+        s.push(if mode & r != 0 { 'r' } else { '-' });
+        s.push(if mode & w != 0 { 'w' } else { '-' });
+        s.push(match (mode & x != 0, mode & special != 0) {
+            (true, true) => set.to_ascii_lowercase(),
+            (false, true) => set.to_ascii_uppercase(),
+            (true, false) => 'x',
+            (false, false) => '-',
+        });
+    }
+
+    let type_char = match mode & 0o170000 {
+        0o100000 => '-',
+        0o040000 => 'd',
+        0o120000 => 'l',
+        0o060000 => 'b',
+        0o020000 => 'c',
+        0o010000 => 'p',
+        0o140000 => 's',
+        _ => '?',
+    };
+
+    let mut s = String::with_capacity(10);
+    s.push(type_char);
+    push_triad(&mut s, mode, 0o400, 0o200, 0o100, 0o4000, 's');
+    push_triad(&mut s, mode, 0o040, 0o020, 0o010, 0o2000, 's');
+    push_triad(&mut s, mode, 0o004, 0o002, 0o001, 0o1000, 't');
+    s
+}
+
+/// Stream a file's contents through MD5, returning the lowercase hex digest.
+/// Non-regular files hash to the `"0"` placeholder.
+#[cfg(all(feature = "from_path", feature = "md5"))]
+fn md5_of_file(path: &std::path::Path, meta: &std::fs::Metadata) -> std::io::Result<String> {
+    use std::io::Read;
+    if !meta.file_type().is_file() {
+        return Ok("0".to_owned());
+    }
+    let mut file = std::fs::File::open(path)?;
+    let mut context = md5::Context::new();
+    let mut buf = [0u8; 8192];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        context.consume(&buf[..n]);
+    }
+    Ok(format!("{:x}", context.compute()))
+}
+
+#[cfg(feature = "from_path")]
+impl Bodyfile3Line {
+    /// Build a bodyfile line by `stat`-ing a single path, turning the crate
+    /// into a one-call `fls`-style generator.
+    ///
+    /// The file is inspected with [`std::fs::symlink_metadata`] so that
+    /// symlinks are described by their own metadata rather than their target.
+    /// `name` is filled with `path`; `size`, `uid`, `gid`, the permission bits
+    /// and the atime/mtime/ctime/crtime fields are mapped from the metadata.
+    /// On platforms that do not expose a creation time, `crtime` is set to the
+    /// `-1` "unset" sentinel used by [`Bodyfile3Line::new`]. On non-Unix
+    /// platforms `uid`, `gid` and `inode` are `0`, `ctime` is `-1`, and the
+    /// mode string carries only the file type, with no permission bits set.
+    /// The MD5 is computed
+    /// by streaming the file contents when the `md5` feature is enabled, and
+    /// defaults to `"0"` otherwise.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use bodyfile::Bodyfile3Line;
+    /// use std::path::Path;
+    ///
+    /// let bf = Bodyfile3Line::from_path(Path::new("/etc/passwd")).unwrap();
+    /// println!("{}", bf.to_string());
+    /// ```
+    pub fn from_path(path: &std::path::Path) -> std::io::Result<Self> {
+        use std::time::UNIX_EPOCH;
+
+        let meta = std::fs::symlink_metadata(path)?;
+
+        let crtime = match meta.created() {
+            Ok(st) => match st.duration_since(UNIX_EPOCH) {
+                Ok(d) => d.as_secs() as i64,
+                Err(e) => -(e.duration().as_secs() as i64),
+            },
+            Err(_) => -1,
+        };
+
+        #[cfg(unix)]
+        let (uid, gid, size, mode_bits, inode, atime, mtime, ctime) = {
+            use std::os::unix::fs::MetadataExt;
+            (
+                meta.uid() as i64,
+                meta.gid() as i64,
+                meta.size() as i64,
+                meta.mode(),
+                meta.ino().to_string(),
+                meta.atime(),
+                meta.mtime(),
+                meta.ctime(),
+            )
+        };
+
+        #[cfg(not(unix))]
+        let (uid, gid, size, mode_bits, inode, atime, mtime, ctime) = {
+            let to_unix = |t: std::io::Result<std::time::SystemTime>| match t {
+                Ok(st) => match st.duration_since(UNIX_EPOCH) {
+                    Ok(d) => d.as_secs() as i64,
+                    Err(e) => -(e.duration().as_secs() as i64),
+                },
+                Err(_) => -1,
+            };
+            let type_bits = if meta.file_type().is_dir() {
+                0o040000
+            } else if meta.file_type().is_symlink() {
+                0o120000
+            } else {
+                0o100000
+            };
+            (
+                0i64,
+                0i64,
+                meta.len() as i64,
+                type_bits,
+                "0".to_owned(),
+                to_unix(meta.accessed()),
+                to_unix(meta.modified()),
+                -1i64,
+            )
+        };
+
+        #[cfg(feature = "md5")]
+        let md5 = md5_of_file(path, &meta)?;
+        #[cfg(not(feature = "md5"))]
+        let md5 = "0".to_owned();
+
+        Ok(Self {
+            md5,
+            name: path.to_string_lossy().into_owned(),
+            inode,
+            mode_as_string: mode_string_from_bits(mode_bits),
+            uid,
+            gid,
+            size,
+            atime,
+            mtime,
+            ctime,
+            crtime,
+        })
+    }
+}
+
+/// Convert a raw bodyfile timestamp into an [`OffsetDateTime`].
+///
+/// The `-1` and `0` sentinels mean "unset" and map to `None`; every other
+/// value — including genuinely negative, pre-1970 timestamps — is converted
+/// faithfully so that "unset" stays distinct from "legitimately negative".
+fn timestamp_to_datetime(ts: i64) -> Option<OffsetDateTime> {
+    if ts == -1 || ts == 0 {
+        return None;
+    }
+    OffsetDateTime::from_unix_timestamp(ts).ok()
+}
+
+impl Bodyfile3Line {
+    /// Return this time field as an [`OffsetDateTime`], or `None` when the
+    /// field holds the `-1`/`0` "unset" sentinel.
+    ///
+    /// Pre-epoch (negative) timestamps other than `-1` are returned as a `Some`
+    /// with a genuine negative Unix time — they are never clamped. A stored
+    /// value of exactly `-1` or `0` always reads back as `None`.
+    ///
+    /// # Example
+    /// ```
+    /// use bodyfile::Bodyfile3Line;
+    /// use time::OffsetDateTime;
+    ///
+    /// // a fresh line has every time unset
+    /// assert!(Bodyfile3Line::new().atime_datetime().is_none());
+    ///
+    /// // a pre-1970 modification time round-trips as a genuine negative value
+    /// let pre_epoch = OffsetDateTime::from_unix_timestamp(-100).unwrap();
+    /// let bf = Bodyfile3Line::new().with_mtime_datetime(pre_epoch);
+    /// assert_eq!(bf.get_mtime(), -100);
+    /// assert_eq!(bf.mtime_datetime().unwrap().unix_timestamp(), -100);
+    /// assert!(bf.to_string().contains("|-100|"));
+    /// ```
+    #[duplicate(
+        method_name        attribute_name;
+        [atime_datetime]   [atime];
+        [mtime_datetime]   [mtime];
+        [ctime_datetime]   [ctime];
+        [crtime_datetime]  [crtime];
+    )]
+    pub fn method_name(&self) -> Option<OffsetDateTime> {
+        timestamp_to_datetime(self.attribute_name)
+    }
+
+    /// Set this time field from a typed [`OffsetDateTime`], storing its Unix
+    /// timestamp (which may be negative for pre-epoch times). A datetime whose
+    /// Unix timestamp is `-1` or `0` is stored as that value, which the
+    /// matching `*_datetime` accessor reports as unset.
+    #[duplicate(
+        method_name               attribute_name;
+        [with_atime_datetime]     [atime];
+        [with_mtime_datetime]     [mtime];
+        [with_ctime_datetime]     [ctime];
+        [with_crtime_datetime]    [crtime];
+    )]
+    pub fn method_name(mut self, datetime: OffsetDateTime) -> Self {
+        self.attribute_name = datetime.unix_timestamp();
+        self
+    }
+}
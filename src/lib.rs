@@ -0,0 +1,8 @@
+mod bodyfile3;
+pub use bodyfile3::*;
+
+mod mactime;
+pub use mactime::*;
+
+mod bodyfile1;
+pub use bodyfile1::*;
@@ -0,0 +1,157 @@
+use time::format_description::well_known::Rfc3339;
+use time::{OffsetDateTime, UtcOffset};
+
+use crate::Bodyfile3Line;
+
+/// A single row of a `mactime` timeline: one distinct timestamp belonging to
+/// one file, together with the MACB flags telling which of the four times
+/// (`m`time, `a`time, `c`time, crtime/`b`) share that value.
+struct MactimeEvent {
+    timestamp: i64,
+    macb: [bool; 4],
+    size: i64,
+    mode: String,
+    uid: i64,
+    gid: i64,
+    inode: String,
+    name: String,
+}
+
+impl MactimeEvent {
+    /// Render the four MACB positions, in the order `m`, `a`, `c`, `b`, using a
+    /// `.` wherever that time does not match this event's timestamp.
+    fn macb_string(&self) -> String {
+        const LETTERS: [char; 4] = ['m', 'a', 'c', 'b'];
+        self.macb
+            .iter()
+            .enumerate()
+            .map(|(i, &on)| if on { LETTERS[i] } else { '.' })
+            .collect()
+    }
+
+    fn format(&self, offset: UtcOffset) -> String {
+        let date = OffsetDateTime::from_unix_timestamp(self.timestamp)
+            .map(|dt| dt.to_offset(offset))
+            .ok()
+            .and_then(|dt| dt.format(&Rfc3339).ok())
+            .unwrap_or_else(|| self.timestamp.to_string());
+        format!(
+            "{} | {} | {} | {} | {} | {} | {} | {}",
+            date,
+            self.size,
+            self.macb_string(),
+            self.mode,
+            self.uid,
+            self.gid,
+            self.inode,
+            self.name
+        )
+    }
+}
+
+/// Consumes an iterator of [`Bodyfile3Line`] and produces a sorted `mactime`
+/// timeline, the way TSK's `mactime` tool does.
+///
+/// For each line one event is emitted per distinct timestamp among
+/// atime/mtime/ctime/crtime, ignoring the `0` and `-1` ("unset") sentinels.
+/// When several of those four times are equal they collapse into a single
+/// event whose MACB flag string carries a letter in each matching position
+/// (ordered `m`, `a`, `c`, `b`) and a `.` otherwise. Events are sorted
+/// ascending by timestamp, stably, with ties broken by name, and rendered as
+///
+/// ```text
+/// date | size | MACB | mode | uid | gid | inode | name
+/// ```
+///
+/// Because the output is globally sorted by timestamp, all events are buffered
+/// in memory to perform the sort; only the final formatting is lazy, with each
+/// row rendered as it is pulled through [`Iterator`] rather than up front. The
+/// timezone offset used for date formatting is configurable via
+/// [`Mactime::with_offset`].
+///
+/// # Example
+/// ```
+/// use bodyfile::{Bodyfile3Line, Mactime};
+///
+/// // mtime and atime share the same value, so they collapse into one event.
+/// let line = Bodyfile3Line::from_values(
+///     "0".to_owned(), "/a".to_owned(), "1".to_owned(), "-".to_owned(),
+///     0, 0, 0, 100, 100, -1, -1,
+/// );
+/// let rows: Vec<String> = Mactime::new(vec![line]).collect();
+/// assert_eq!(rows.len(), 1);
+/// assert!(rows[0].contains("ma.."));
+/// ```
+pub struct Mactime {
+    events: std::vec::IntoIter<MactimeEvent>,
+    offset: UtcOffset,
+}
+
+impl Mactime {
+    /// Build a timeline using UTC for date formatting.
+    pub fn new<I>(lines: I) -> Self
+    where
+        I: IntoIterator<Item = Bodyfile3Line>,
+    {
+        Self::with_offset(lines, UtcOffset::UTC)
+    }
+
+    /// Build a timeline, rendering dates in the given timezone `offset`.
+    pub fn with_offset<I>(lines: I, offset: UtcOffset) -> Self
+    where
+        I: IntoIterator<Item = Bodyfile3Line>,
+    {
+        let mut events = Vec::new();
+        for line in lines {
+            let times = [
+                line.get_mtime(),
+                line.get_atime(),
+                line.get_ctime(),
+                line.get_crtime(),
+            ];
+
+            // group the four times by value so that equal times become one event
+            let mut grouped: Vec<(i64, [bool; 4])> = Vec::new();
+            for (i, &t) in times.iter().enumerate() {
+                if t == 0 || t == -1 {
+                    continue;
+                }
+                if let Some(slot) = grouped.iter_mut().find(|(v, _)| *v == t) {
+                    slot.1[i] = true;
+                } else {
+                    let mut flags = [false; 4];
+                    flags[i] = true;
+                    grouped.push((t, flags));
+                }
+            }
+
+            for (timestamp, macb) in grouped {
+                events.push(MactimeEvent {
+                    timestamp,
+                    macb,
+                    size: line.get_size(),
+                    mode: line.get_mode().to_owned(),
+                    uid: line.get_uid(),
+                    gid: line.get_gid(),
+                    inode: line.get_inode().to_owned(),
+                    name: line.get_name().to_owned(),
+                });
+            }
+        }
+
+        events.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then_with(|| a.name.cmp(&b.name)));
+
+        Self {
+            events: events.into_iter(),
+            offset,
+        }
+    }
+}
+
+impl Iterator for Mactime {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.events.next().map(|event| event.format(self.offset))
+    }
+}